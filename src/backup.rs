@@ -0,0 +1,117 @@
+use bip0039::{English, Mnemonic};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+use zcash_client_backend::data_api::{Account as _, WalletRead};
+use zcash_client_sqlite::WalletDb;
+use zcash_keys::keys::UnifiedAddressRequest;
+
+use crate::{
+    balance::select_account,
+    config::{encrypt_armored, get_wallet_network, WalletConfig},
+    data::{get_db_paths, Network},
+};
+
+#[derive(Debug, Args)]
+pub(crate) struct BackupOptions {
+    /// The UUID of the account if multiple exist
+    account_id: Option<Uuid>,
+
+    /// Age identity file to decrypt the seed with (same one
+    /// passed to `init`)
+    #[arg(short, long)]
+    identity: String,
+
+    /// Age recipient(s) to re-encrypt the backup bundle to, in
+    /// addition to the identity's own public key. May be
+    /// repeated
+    #[arg(short, long)]
+    recipient: Vec<String>,
+
+    /// Where to write the armored backup bundle
+    #[arg(short, long)]
+    output: PathBuf,
+}
+
+impl BackupOptions {
+    pub(crate) async fn run(
+        self,
+        wallet_dir: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        let params = get_wallet_network(wallet_dir.as_ref())?;
+        let (_, db_data) = get_db_paths(wallet_dir.as_ref());
+        let db_data = WalletDb::for_path(db_data, params.clone(), (), ())?;
+        let account = select_account(&db_data, self.account_id)?;
+
+        let mnemonic: Mnemonic<English> =
+            WalletConfig::read_mnemonic(wallet_dir.as_ref(), &self.identity)?;
+        let birthday = db_data.get_account_birthday(account.id())?;
+        // Captured so `restore` can recreate the account under its
+        // original name and pick up where its diversified addresses
+        // left off, instead of making the user retype the name and
+        // losing the last address it had handed out
+        let last_address = db_data
+            .get_last_generated_address_matching(
+                account.id(),
+                UnifiedAddressRequest::AllAvailableKeys,
+            )?
+            .map(|address| address.encode(&params));
+        let bundle = BackupBundle {
+            account_id: *account.id().expose_uuid(),
+            account_name: account.name().map(|name| name.to_string()),
+            // Recorded so `restore` can validate/default its --network
+            // flag instead of trusting an unverified one, which would
+            // otherwise silently reconstruct the wrong HRPs/consensus
+            // params for this account
+            network: Network::from(params.clone()).name().to_string(),
+            mnemonic: mnemonic.phrase().to_string(),
+            birthday: u32::from(birthday),
+            last_address,
+        };
+
+        let mut recipients: Vec<Box<dyn age::Recipient>> =
+            age::IdentityFile::from_file(self.identity)?
+                .to_recipients()?
+                .into_iter()
+                .map(|r| r as _)
+                .collect();
+        for recipient in &self.recipient {
+            recipients.push(Box::new(
+                recipient
+                    .parse::<age::x25519::Recipient>()
+                    .map_err(|e| anyhow::anyhow!("Invalid age recipient: {e}"))?,
+            ));
+        }
+
+        let bundle_str = toml::to_string(&bundle)
+            .map_err(|_| anyhow::anyhow!("error serializing backup bundle"))?;
+        let ciphertext = encrypt_armored(
+            recipients.iter().map(|r| r.as_ref()),
+            bundle_str.as_bytes(),
+        )?;
+        std::fs::write(&self.output, ciphertext)?;
+        println!("Wrote encrypted backup to {}", self.output.display());
+        Ok(())
+    }
+}
+
+/// The structured record re-encrypted into a backup bundle. Just
+/// enough to reconstruct a synced-capable wallet via `restore`.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct BackupBundle {
+    pub(crate) account_id: Uuid,
+    /// The account's name at backup time, if it had one. `restore`
+    /// uses this instead of requiring the name be retyped.
+    pub(crate) account_name: Option<String>,
+    /// The network the account was backed up from (\"main\" or
+    /// \"test\"). `restore` validates/defaults its --network flag
+    /// against this instead of trusting an unverified one.
+    pub(crate) network: String,
+    pub(crate) mnemonic: String,
+    pub(crate) birthday: u32,
+    /// The last unified address issued to the account, if any, so
+    /// `restore` can re-derive it instead of leaving the restored
+    /// wallet stuck on its default address.
+    pub(crate) last_address: Option<String>,
+}
@@ -3,11 +3,16 @@ use clap::Args;
 use colored::Colorize;
 use iso_currency::Currency;
 use rust_decimal::{prelude::FromPrimitive, Decimal};
+use std::str::FromStr;
+use std::collections::HashMap;
+use std::sync::OnceLock;
 use textwrap::{fill, Options};
-use tracing::{info, warn};
+use tokio::sync::Mutex;
+use tracing::info;
 use uuid::Uuid;
 use zcash_client_backend::{
     data_api::{wallet::ConfirmationsPolicy, Account as _, WalletRead},
+    proto::service,
     tor,
 };
 use zcash_client_sqlite::AccountUuid;
@@ -21,7 +26,7 @@ use crate::{
     config::get_wallet_network,
     data::get_db_paths,
     error,
-    remote::tor_client,
+    remote::{tor_client, Servers},
     ui::{format_zec, TEXT_WIDTH},
 };
 
@@ -55,6 +60,25 @@ fn parse_currency(data: &str) -> Result<Currency, String> {
         .ok_or_else(|| format!("Invalid currency '{data}'"))
 }
 
+/// A point in the past to value the balance as of: either a block
+/// height (resolved to a timestamp via `get_tree_state`) or a date
+/// handed straight to the historical price feed.
+#[derive(Debug, Clone, Copy)]
+enum HistoricalPoint {
+    Height(u32),
+    Date(chrono::NaiveDate),
+}
+
+fn parse_at(data: &str) -> Result<HistoricalPoint, String> {
+    if let Ok(height) = data.parse::<u32>() {
+        Ok(HistoricalPoint::Height(height))
+    } else {
+        chrono::NaiveDate::parse_from_str(data, "%Y-%m-%d")
+            .map(HistoricalPoint::Date)
+            .map_err(|_| format!("Invalid height or date '{data}'"))
+    }
+}
+
 // Options accepted for the `balance` command
 #[derive(Debug, Args)]
 pub(crate) struct BalanceOptions {
@@ -65,6 +89,19 @@ pub(crate) struct BalanceOptions {
     #[arg(long)]
     #[arg(value_parser = parse_currency)]
     convert: Option<Currency>,
+
+    /// Value the balance using the historical ZEC price at a past
+    /// block height or date (\"YYYY-MM-DD\"), instead of spot price.
+    /// Requires --convert, which it values
+    #[arg(long, requires = "convert")]
+    #[arg(value_parser = parse_at)]
+    at: Option<HistoricalPoint>,
+
+    /// The server to fetch a height's timestamp from when
+    /// --at is given a block height (default is \"ecc\")
+    #[arg(short, long)]
+    #[arg(default_value = "ecc", value_parser = Servers::parse)]
+    server: Servers,
 }
 
 impl BalanceOptions {
@@ -85,7 +122,30 @@ impl BalanceOptions {
         // Retrieve the exchange rate if we need to
         let printer = if let Some(currency) = self.convert {
             let tor = tor_client(wallet_dir.as_ref()).await?;
-            ValuePrinter::with_exchange_rate(&tor, currency).await?
+            match self.at {
+                Some(at) => {
+                    let date = match at {
+                        HistoricalPoint::Date(date) => date,
+                        HistoricalPoint::Height(height) => {
+                            let server = self.server.pick(params)?;
+                            let mut client = server
+                                .connect(|| tor_client(wallet_dir.as_ref()))
+                                .await?;
+                            let request = service::BlockId {
+                                height: u64::from(height),
+                                ..Default::default()
+                            };
+                            let treestate =
+                                client.get_tree_state(request).await?.into_inner();
+                            chrono::DateTime::from_timestamp(treestate.time.into(), 0)
+                                .ok_or_else(|| anyhow!("Invalid block timestamp"))?
+                                .date_naive()
+                        }
+                    };
+                    ValuePrinter::with_historical_rate(&tor, currency, date).await?
+                }
+                None => ValuePrinter::with_exchange_rate(&tor, currency).await?,
+            }
         } else {
             ValuePrinter::ZecOnly
         };
@@ -165,6 +225,10 @@ enum ValuePrinter {
     ZecOnly,
 }
 
+// Cached for the process lifetime: the fiat leg rarely moves within a
+// single invocation and the reference feed has no reason to be hit twice
+static FIAT_RATE_CACHE: OnceLock<Mutex<HashMap<Currency, Decimal>>> = OnceLock::new();
+
 impl ValuePrinter {
     async fn with_exchange_rate(
         tor: &tor::Client,
@@ -173,14 +237,24 @@ impl ValuePrinter {
         info!("Fetching {:?}/ZEC exchange rate", currency);
         let exchanges = tor::http::cryptex::Exchanges::unauthenticated_known_with_gemini_trusted();
         let usd_zec = tor.get_latest_zec_to_usd_rate(&exchanges).await?;
-        if currency == Currency::USD {
-            let rate = usd_zec;
-            info!("Current {:?}/ZEC exchange rate: {}", currency, rate);
-            Ok(Self::WithConversion { currency, rate })
-        } else {
-            warn!("{:?}/ZEC exchange rate is unsupported", currency);
-            Ok(Self::ZecOnly)
-        }
+        let rate = usd_zec * fiat_cross_rate(tor, currency).await?;
+        info!("Current {:?}/ZEC exchange rate: {}", currency, rate);
+        Ok(Self::WithConversion { currency, rate })
+    }
+
+    async fn with_historical_rate(
+        tor: &tor::Client,
+        currency: Currency,
+        date: chrono::NaiveDate,
+    ) -> anyhow::Result<Self> {
+        info!("Fetching {:?}/ZEC exchange rate as of {}", currency, date);
+        let usd_zec = historical_zec_to_usd_rate(tor, date).await?;
+        let rate = usd_zec * fiat_cross_rate(tor, currency).await?;
+        info!(
+            "{:?}/ZEC exchange rate as of {}: {}",
+            currency, date, rate
+        );
+        Ok(Self::WithConversion { currency, rate })
     }
 
     fn format(&self, value: Zatoshis) -> String {
@@ -198,3 +272,54 @@ impl ValuePrinter {
         }
     }
 }
+
+/// Fetches the USD→`currency` cross rate from a fiat reference feed
+/// over Tor. USD itself is trivially 1:1 and never hits the network.
+async fn fiat_cross_rate(
+    tor: &tor::Client,
+    currency: Currency,
+) -> anyhow::Result<Decimal> {
+    if currency == Currency::USD {
+        return Ok(Decimal::ONE);
+    }
+    let cache = FIAT_RATE_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(rate) = cache.lock().await.get(&currency) {
+        return Ok(*rate);
+    }
+    // The feed returns every currency's rate in one response; there's
+    // no per-currency endpoint to scope the request to
+    let body = tor.get("https://open.er-api.com/v6/latest/USD").await?;
+    let rates: serde_json::Value = serde_json::from_slice(body.as_ref())?;
+    let rate = rates["rates"][currency.code()]
+        .as_number()
+        .ok_or_else(|| anyhow!("Fiat feed has no rate for {:?}", currency))?;
+    // Parse the feed's number as a string rather than `as_f64`, so a
+    // rate like 0.1 doesn't pick up binary floating-point imprecision
+    // on its way into a `Decimal`
+    let rate = Decimal::from_str(&rate.to_string())
+        .map_err(|_| anyhow!("Fiat feed returned an invalid rate for {:?}", currency))?;
+    cache.lock().await.insert(currency, rate);
+    Ok(rate)
+}
+
+/// Fetches the ZEC→USD rate as of a past date from a historical
+/// price endpoint over Tor (as opposed to `cryptex`'s spot rate).
+async fn historical_zec_to_usd_rate(
+    tor: &tor::Client,
+    date: chrono::NaiveDate,
+) -> anyhow::Result<Decimal> {
+    let url = format!(
+        "https://api.coingecko.com/api/v3/coins/zcash/history?date={}",
+        date.format("%d-%m-%Y")
+    );
+    let body = tor.get(&url).await?;
+    let history: serde_json::Value = serde_json::from_slice(body.as_ref())?;
+    let rate = history["market_data"]["current_price"]["usd"]
+        .as_number()
+        .ok_or_else(|| anyhow!("Historical price feed has no rate for {}", date))?;
+    // Same reasoning as `fiat_cross_rate`: go through the number's
+    // string form instead of `as_f64` to avoid binary float imprecision
+    Decimal::from_str(&rate.to_string()).map_err(|_| {
+        anyhow!("Historical price feed returned an invalid rate for {}", date)
+    })
+}
@@ -0,0 +1,99 @@
+use clap::Args;
+use std::path::PathBuf;
+use tracing::info;
+use zcash_client_backend::proto::service;
+use zcash_protocol::consensus;
+
+use crate::{
+    config::get_wallet_network,
+    remote::{tor_client, Servers},
+};
+
+#[derive(Debug, Args)]
+pub(crate) struct BroadcastOptions {
+    /// The signed raw transaction(s) file produced by `sign`
+    #[arg(short, long)]
+    transaction: PathBuf,
+
+    /// The server to broadcast the transaction through
+    /// (default is \"ecc\")
+    #[arg(short, long)]
+    #[arg(default_value = "ecc", value_parser = Servers::parse)]
+    server: Servers,
+}
+
+impl BroadcastOptions {
+    pub(crate) async fn run(
+        self,
+        wallet_dir: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        let params = get_wallet_network(wallet_dir.as_ref())?;
+        let server = self.server.pick(params)?;
+        let mut client =
+            server.connect(|| tor_client(wallet_dir.as_ref())).await?;
+
+        let mut data = &std::fs::read(&self.transaction)?[..];
+        let mut sent = 0;
+        while !data.is_empty() {
+            if data.len() < 4 {
+                anyhow::bail!("Truncated transaction file: missing length prefix");
+            }
+            let (len, rest) = data.split_at(4);
+            let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+            if rest.len() < len {
+                anyhow::bail!(
+                    "Truncated transaction file: expected {len} bytes, found {}",
+                    rest.len()
+                );
+            }
+            let (raw_tx, rest) = rest.split_at(len);
+            let response = client
+                .send_transaction(service::RawTransaction {
+                    data: raw_tx.to_vec(),
+                })
+                .await?
+                .into_inner();
+            if response.error_code != 0 {
+                anyhow::bail!("Server rejected transaction: {}", response.error_message);
+            }
+            sent += 1;
+            data = rest;
+        }
+        info!("Broadcast {sent} signed transaction(s)");
+        println!("Broadcast {sent} transaction(s) from {}", self.transaction.display());
+        Ok(())
+    }
+}
+
+/// Connects to `server` and broadcasts each of `txids`, looking up its
+/// raw bytes via `get_raw_tx`. Shared by `send` and `shield`, which
+/// differ in how they build the proposal and what verb they print
+/// for the resulting transactions.
+pub(crate) async fn broadcast_proposed_transactions<'a, Id>(
+    wallet_dir: Option<&String>,
+    params: consensus::Network,
+    server: Servers,
+    txids: impl IntoIterator<Item = &'a Id>,
+    mut get_raw_tx: impl FnMut(Id) -> Result<Vec<u8>, anyhow::Error>,
+) -> Result<(), anyhow::Error>
+where
+    Id: std::fmt::Display + Copy + 'a,
+{
+    let server = server.pick(params)?;
+    let mut client = server.connect(|| tor_client(wallet_dir)).await?;
+    for txid in txids {
+        let raw_tx_bytes = get_raw_tx(*txid)?;
+        let response = client
+            .send_transaction(service::RawTransaction { data: raw_tx_bytes })
+            .await?
+            .into_inner();
+        if response.error_code != 0 {
+            anyhow::bail!(
+                "Server rejected transaction {txid}: {}",
+                response.error_message
+            );
+        }
+        info!("Broadcast transaction {txid}");
+    }
+    Ok(())
+}
@@ -1,11 +1,18 @@
-use crate::data::{Network, DEFAULT_WALLET_DIR};
+use crate::{
+    data::{Network, DEFAULT_WALLET_DIR},
+    error,
+};
+use age::secrecy::ExposeSecret as _;
 use anyhow::anyhow;
-use bip0039::Mnemonic;
+use bip0039::{English, Mnemonic};
+use secrecy::{ExposeSecret, SecretVec, Zeroize};
 use serde::{Deserialize, Serialize};
 use std::fs::{self};
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::Path;
+use zcash_keys::keys::UnifiedSpendingKey;
 use zcash_protocol::consensus::{self, BlockHeight};
+use zip32::AccountId;
 
 const KEYS_FILE: &str = "keys.toml";
 
@@ -22,15 +29,90 @@ impl WalletConfig {
         init_wallet_config(
             wallet_dir,
             Some(encrypt_mnemonic(recipients, mnemonic)?),
+            None,
             birthday,
             network,
         )
     }
+
+    /// Persists config for a watch-only/external-signer account, e.g. one
+    /// backed by a Ledger device. There is no mnemonic to encrypt; instead
+    /// `key_source` records where signing is expected to happen so later
+    /// `send`/`sign` flows know to route there.
+    pub(crate) fn init_with_key_source<P: AsRef<Path>>(
+        wallet_dir: Option<P>,
+        key_source: &str,
+        birthday: BlockHeight,
+        network: consensus::Network,
+    ) -> Result<(), anyhow::Error> {
+        init_wallet_config(
+            wallet_dir,
+            None,
+            Some(key_source.to_string()),
+            birthday,
+            network,
+        )
+    }
+
+    /// Decrypts the mnemonic phrase persisted by `init_with_mnemonic`,
+    /// mirroring its recipient handling in reverse.
+    pub(crate) fn read_mnemonic<P: AsRef<Path>>(
+        wallet_dir: Option<P>,
+        identity_path: &str,
+    ) -> Result<Mnemonic<English>, anyhow::Error> {
+        let wallet_dir = wallet_dir
+            .as_ref()
+            .map(|p| p.as_ref())
+            .unwrap_or(DEFAULT_WALLET_DIR.as_ref());
+        let mut keys_file = {
+            let mut p = wallet_dir.to_owned();
+            p.push(KEYS_FILE);
+            fs::File::open(p)
+        }?;
+        let mut config_str = String::new();
+        keys_file.read_to_string(&mut config_str)?;
+        let config: ConfigEncoding = toml::from_str(&config_str)
+            .map_err::<anyhow::Error, _>(|_| anyhow!("error reading wallet config"))?;
+        let ciphertext = config
+            .mnemonic
+            .ok_or_else(|| anyhow!("wallet config has no mnemonic"))?;
+        let identities = age::IdentityFile::from_file(identity_path.to_string())?
+            .into_identities()?;
+        let phrase = decrypt_mnemonic(&identities, &ciphertext)?;
+        Ok(<Mnemonic<English>>::from_phrase(phrase.expose_secret())?)
+    }
+
+    /// Decrypts the mnemonic and derives the account's spending key
+    /// from it, zeroizing the intermediate seed as soon as the key is
+    /// derived. Security-sensitive: this is the one place a stored
+    /// mnemonic turns into spending key material, instead of `send`,
+    /// `shield`, and `sign` each re-deriving it independently.
+    pub(crate) fn derive_spending_key<P: AsRef<Path>>(
+        wallet_dir: Option<P>,
+        identity_path: &str,
+        params: &consensus::Network,
+        account_index: AccountId,
+    ) -> Result<UnifiedSpendingKey, anyhow::Error> {
+        let mnemonic = Self::read_mnemonic(wallet_dir, identity_path)?;
+        let seed = {
+            let mut seed = mnemonic.to_seed("");
+            let secret = seed.to_vec();
+            seed.zeroize();
+            SecretVec::new(secret)
+        };
+        Ok(UnifiedSpendingKey::from_seed(
+            params,
+            seed.expose_secret(),
+            account_index,
+        )
+        .map_err(error::Error::from)?)
+    }
 }
 
 fn init_wallet_config<P: AsRef<Path>>(
     wallet_dir: Option<P>,
     mnemonic: Option<String>,
+    key_source: Option<String>,
     birthday: BlockHeight,
     network: consensus::Network,
 ) -> Result<(), anyhow::Error> {
@@ -51,6 +133,7 @@ fn init_wallet_config<P: AsRef<Path>>(
         mnemonic,
         network: Some(Network::from(network).name().to_string()),
         birthday: Some(u32::from(birthday)),
+        key_source,
     };
     // Seems like we're doing a custom config
     // which is just stringified from toml
@@ -67,11 +150,45 @@ struct ConfigEncoding {
     mnemonic: Option<String>,
     network: Option<String>,
     birthday: Option<u32>,
+    /// Where the signing key for this account lives, e.g. `"ledger"`.
+    /// Absent for accounts whose mnemonic is stored locally.
+    key_source: Option<String>,
+}
+
+fn decrypt_mnemonic(
+    identities: &[Box<dyn age::Identity>],
+    ciphertext: &str,
+) -> Result<age::secrecy::SecretString, anyhow::Error> {
+    let phrase = decrypt_armored(identities, ciphertext)?;
+    Ok(age::secrecy::SecretString::new(String::from_utf8(phrase)?))
 }
 
 fn encrypt_mnemonic<'a>(
     recipients: impl Iterator<Item = &'a dyn age::Recipient>,
     mnemonic: &Mnemonic,
+) -> Result<String, anyhow::Error> {
+    encrypt_armored(recipients, mnemonic.phrase().as_bytes())
+}
+
+/// Decrypts an age-armored ciphertext produced by `encrypt_armored`.
+/// Shared by the mnemonic path above and `backup`/`restore`'s bundle.
+pub(crate) fn decrypt_armored(
+    identities: &[Box<dyn age::Identity>],
+    ciphertext: &str,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let decryptor =
+        age::Decryptor::new(age::armor::ArmoredReader::new(ciphertext.as_bytes()))?;
+    let mut plaintext = vec![];
+    let mut reader = decryptor.decrypt(identities.iter().map(|i| i.as_ref() as _))?;
+    reader.read_to_end(&mut plaintext)?;
+    Ok(plaintext)
+}
+
+/// Encrypts `plaintext` to `recipients` as age-armored ASCII. Shared by
+/// the mnemonic path above and `backup`/`restore`'s bundle.
+pub(crate) fn encrypt_armored<'a>(
+    recipients: impl Iterator<Item = &'a dyn age::Recipient>,
+    plaintext: &[u8],
 ) -> Result<String, anyhow::Error> {
     let encryptor = age::Encryptor::with_recipients(recipients)?;
     let mut ciphertext = vec![];
@@ -80,7 +197,7 @@ fn encrypt_mnemonic<'a>(
             &mut ciphertext,
             age::armor::Format::AsciiArmor,
         )?)?;
-    writer.write_all(mnemonic.phrase().as_bytes())?;
+    writer.write_all(plaintext)?;
     writer.finish().and_then(|armor| armor.finish())?;
     Ok(String::from_utf8(ciphertext).expect("armor is valid UTF-8"))
 }
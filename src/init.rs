@@ -4,8 +4,9 @@ use clap::Args;
 use secrecy::{ExposeSecret as _, SecretString, SecretVec, Zeroize};
 use tokio::io::AsyncWriteExt;
 use tonic::transport::Channel;
+use uuid::Uuid;
 use zcash_client_backend::{
-    data_api::{AccountBirthday, WalletWrite},
+    data_api::{Account as _, AccountBirthday, AccountPurpose, WalletWrite},
     proto::service::{
         self, compact_tx_streamer_client::CompactTxStreamerClient,
     },
@@ -16,6 +17,7 @@ use crate::{
     config::WalletConfig,
     data::{init_dbs, Network},
     error,
+    ledger::LedgerDevice,
     remote::{tor_client, Servers},
 };
 
@@ -26,9 +28,20 @@ pub(crate) struct InitOptions {
     name: String,
 
     /// Age identity file to encrypt the mnemonic
-    /// phrase to (generated if it doesn't exist)
-    #[arg(short, long)]
-    identity: String,
+    /// phrase to (generated if it doesn't exist).
+    /// Not used with --ledger, since no seed is stored
+    #[arg(short, long, required_unless_present = "ledger")]
+    identity: Option<String>,
+
+    /// Derive the account from a connected Ledger device over
+    /// USB-HID instead of a locally stored mnemonic
+    #[arg(long, conflicts_with = "identity")]
+    ledger: bool,
+
+    /// Account index to derive from the Ledger device
+    /// (default is 0). Ignored without --ledger
+    #[arg(long, default_value_t = 0)]
+    account_index: u32,
 
     /// The wallet's birthday
     /// (default is current chain height)
@@ -71,10 +84,41 @@ impl InitOptions {
             .height
             .try_into()
             .expect("block heights must fit into u32");
-        let recipients = if tokio::fs::try_exists(&opts.identity).await? {
+        if opts.ledger {
+            let birthday = Self::get_wallet_birthday(
+                client,
+                opts.birthday
+                    .unwrap_or(chain_tip.saturating_sub(100))
+                    .into(),
+                None,
+            )
+            .await?;
+            // The seed never leaves the device; we only ever
+            // see the account's unified full viewing key
+            let ufvk = LedgerDevice::connect()?
+                .get_unified_full_viewing_key(&params, opts.account_index)?;
+            WalletConfig::init_with_key_source(
+                wallet_dir.as_ref(),
+                "ledger",
+                birthday.height(),
+                opts.network.into(),
+            )?;
+            let account_id = Self::init_watch_only_dbs(
+                params,
+                wallet_dir.as_ref(),
+                &opts.name,
+                &ufvk,
+                birthday,
+            )?;
+            println!("Created account {account_id}");
+            return Ok(());
+        }
+        let identity_path =
+            opts.identity.expect("required unless --ledger is set");
+        let recipients = if tokio::fs::try_exists(&identity_path).await? {
             // Seems like age encryption library used is < 1 which is cautioned
             // to be used for testing purposes only and not necessarily secure
-            age::IdentityFile::from_file(opts.identity)?.to_recipients()?
+            age::IdentityFile::from_file(identity_path)?.to_recipients()?
         } else {
             // Better understand what an age identity is. When we don't have
             // one we create it for the first time. Seems like it's just some
@@ -86,7 +130,7 @@ impl InitOptions {
             let identity = age::x25519::Identity::generate();
             let recipient = identity.to_public();
             // Write it to the path so we have it for next time
-            let mut f = tokio::fs::File::create_new(opts.identity).await?;
+            let mut f = tokio::fs::File::create_new(identity_path).await?;
             // All writing logic for the key we want unsafely save locally
             // so we can encrypt/decrypt the seed phrase we generate
             f.write_all(
@@ -155,14 +199,16 @@ impl InitOptions {
             // memory, read/written only in limited scopes.
             SecretVec::new(secret)
         };
-        Self::init_dbs(
+        let account_id = Self::init_dbs(
             params,
             wallet_dir.as_ref(),
             &opts.name,
             &seed,
             birthday,
             None,
-        )
+        )?;
+        println!("Created account {account_id}");
+        Ok(())
     }
 
     pub(crate) async fn get_wallet_birthday(
@@ -193,14 +239,42 @@ impl InitOptions {
         seed: &SecretVec<u8>,
         birthday: AccountBirthday,
         key_source: Option<&str>,
-    ) -> Result<(), anyhow::Error> {
+    ) -> Result<Uuid, anyhow::Error> {
         // Initialize the block and wallet DBs. Better
         // understand how this is used and what we're storing
         // in here initially that's not in a config file.
         let mut db_data = init_dbs(params, wallet_dir)?;
         // How is the seed protected in this database? It doesn't seem
         // like it would be but maybe I'm missing something.
-        db_data.create_account(account_name, seed, &birthday, key_source)?;
-        Ok(())
+        let (account, _usk) =
+            db_data.create_account(account_name, seed, &birthday, key_source)?;
+        // create_account mints its own UUID; callers need it back so
+        // they don't keep referring to a source account that this
+        // wallet never actually created
+        Ok(*account.id().expose_uuid())
+    }
+
+    /// Like `init_dbs`, but for a watch-only account backed by an
+    /// externally-held spending key (e.g. a Ledger device). No seed
+    /// is ever passed in; the wallet only learns the viewing key.
+    fn init_watch_only_dbs(
+        params: impl Parameters + 'static,
+        wallet_dir: Option<&String>,
+        account_name: &str,
+        ufvk: &zcash_keys::keys::UnifiedFullViewingKey,
+        birthday: AccountBirthday,
+    ) -> Result<Uuid, anyhow::Error> {
+        let mut db_data = init_dbs(params, wallet_dir)?;
+        let account = db_data.import_account_ufvk(
+            account_name,
+            ufvk,
+            &birthday,
+            AccountPurpose::Spending { derivation: None },
+            Some("ledger"),
+        )?;
+        // Same reasoning as `init_dbs`: this is the only place the
+        // account's UUID is ever produced, and there's no accounts-list
+        // command to recover it later
+        Ok(*account.id().expose_uuid())
     }
 }
@@ -0,0 +1,54 @@
+use ledger_apdu::{APDUAnswer, APDUCommand, APDUErrorCode};
+use ledger_transport_hid::{hidapi::HidApi, TransportNativeHID};
+use zcash_keys::keys::UnifiedFullViewingKey;
+use zcash_protocol::consensus::Parameters;
+
+// These match the Zcash Ledger app's APDU interface; see
+// https://github.com/Zondax/ledger-zcash for the reference implementation
+const CLA_ZCASH: u8 = 0xe0;
+const INS_GET_UFVK: u8 = 0x04;
+
+/// A connection to a Ledger device running the Zcash app, used to
+/// derive account-level key material without it ever touching disk.
+pub(crate) struct LedgerDevice {
+    transport: TransportNativeHID,
+}
+
+impl LedgerDevice {
+    pub(crate) fn connect() -> Result<Self, anyhow::Error> {
+        let api = HidApi::new()?;
+        let transport = TransportNativeHID::new(&api)
+            .map_err(|e| anyhow::anyhow!("Could not connect to Ledger device: {e}"))?;
+        Ok(Self { transport })
+    }
+
+    /// Fetches the unified full viewing key for the given account
+    /// index from the connected device's Zcash app.
+    pub(crate) fn get_unified_full_viewing_key(
+        &self,
+        params: &(impl Parameters + 'static),
+        account_index: u32,
+    ) -> Result<UnifiedFullViewingKey, anyhow::Error> {
+        let command = APDUCommand {
+            cla: CLA_ZCASH,
+            ins: INS_GET_UFVK,
+            p1: 0,
+            p2: 0,
+            data: account_index.to_le_bytes().to_vec(),
+        };
+        let response: APDUAnswer<_> = self
+            .transport
+            .exchange(&command)
+            .map_err(|e| anyhow::anyhow!("Ledger device communication error: {e}"))?;
+        if response.error_code() != Ok(APDUErrorCode::NoError) {
+            anyhow::bail!("Ledger device declined to export the viewing key");
+        }
+        // The device returns the UFVK in its bech32m string encoding,
+        // not raw key bytes, so decode expects a &str just like
+        // address.encode(&params) produces one in balance.rs
+        let encoded = std::str::from_utf8(response.data())
+            .map_err(|e| anyhow::anyhow!("Ledger device returned a non-UTF-8 viewing key: {e}"))?;
+        UnifiedFullViewingKey::decode(params, encoded)
+            .map_err(|e| anyhow::anyhow!("Invalid unified full viewing key from device: {e}"))
+    }
+}
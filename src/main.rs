@@ -6,15 +6,31 @@ use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 // Understand modules a bit better and how we want
 // to use them in other parts of the code base
 
+mod backup;
 mod balance;
+mod broadcast;
 mod config;
 mod data;
 mod error;
 mod init;
+mod ledger;
+mod propose;
 mod remote;
+mod restore;
+mod send;
+#[cfg(feature = "transparent-inputs")]
+mod shield;
+mod sign;
+mod sync;
 mod ui;
 
-use crate::{balance::BalanceOptions, init::InitOptions};
+use crate::{
+    backup::BackupOptions, balance::BalanceOptions, broadcast::BroadcastOptions,
+    init::InitOptions, propose::ProposeOptions, restore::RestoreOptions,
+    send::SendOptions, sign::SignOptions, sync::SyncOptions,
+};
+#[cfg(feature = "transparent-inputs")]
+use crate::shield::ShieldOptions;
 
 // Clap is super smart in the documentation it generates
 // for the command-line --help specific to each command
@@ -29,6 +45,31 @@ pub(crate) enum Flag {
 
     /// Get the balance in the wallet
     Balance(BalanceOptions),
+
+    /// Scan compact blocks into the wallet DB
+    Sync(SyncOptions),
+
+    /// Build, prove, and broadcast a shielded payment
+    Send(SendOptions),
+
+    /// Sweep transparent UTXOs into the shielded pool
+    #[cfg(feature = "transparent-inputs")]
+    Shield(ShieldOptions),
+
+    /// Build an unsigned transaction proposal for offline signing
+    Propose(ProposeOptions),
+
+    /// Sign an unsigned proposal produced by `propose`, offline
+    Sign(SignOptions),
+
+    /// Broadcast a signed transaction produced by `sign`
+    Broadcast(BroadcastOptions),
+
+    /// Export an encrypted, portable backup of an account
+    Backup(BackupOptions),
+
+    /// Restore an account from a backup produced by `backup`
+    Restore(RestoreOptions),
 }
 
 #[derive(Debug, Args)]
@@ -87,6 +128,15 @@ fn main() -> Result<(), anyhow::Error> {
             Command::Wallet(Wallet { dir, flag }) => match flag {
                 Flag::Init(options) => options.run(dir).await,
                 Flag::Balance(options) => options.run(dir).await,
+                Flag::Sync(options) => options.run(dir).await,
+                Flag::Send(options) => options.run(dir).await,
+                #[cfg(feature = "transparent-inputs")]
+                Flag::Shield(options) => options.run(dir).await,
+                Flag::Propose(options) => options.run(dir).await,
+                Flag::Sign(options) => options.run(dir).await,
+                Flag::Broadcast(options) => options.run(dir).await,
+                Flag::Backup(options) => options.run(dir).await,
+                Flag::Restore(options) => options.run(dir).await,
             },
         }
     })
@@ -0,0 +1,102 @@
+use clap::Args;
+use prost::Message;
+use rust_decimal::Decimal;
+use std::path::PathBuf;
+use uuid::Uuid;
+use zcash_client_backend::{
+    data_api::wallet::{
+        input_selection::GreedyInputSelector, propose_transfer, ConfirmationsPolicy,
+    },
+    fees::standard::SingleOutputChangeStrategy,
+    proto::proposal,
+};
+use zcash_client_sqlite::WalletDb;
+use zcash_primitives::transaction::fees::zip317::FeeRule;
+use zcash_protocol::value::Zatoshis;
+
+use crate::{
+    balance::select_account, config::get_wallet_network, data::get_db_paths, error,
+    send::build_transaction_request,
+};
+
+// Prefixed onto every proposal file so `sign` can recognize
+// and reject anything that isn't one, before trying to decode it
+pub(crate) const PROPOSAL_MAGIC: &[u8] = b"zecli-proposal-v1";
+
+#[derive(Debug, Args)]
+pub(crate) struct ProposeOptions {
+    /// The UUID of the account if multiple exist
+    account_id: Option<Uuid>,
+
+    /// A raw ZIP-321 payment URI (\"zip321:...\"). Mutually
+    /// exclusive with --to/--amount/--memo
+    #[arg(long, conflicts_with_all = ["to", "amount", "memo"])]
+    uri: Option<String>,
+
+    /// Recipient unified/transparent/shielded address
+    #[arg(long, requires = "amount")]
+    to: Option<String>,
+
+    /// Amount to send, in ZEC
+    #[arg(long, requires = "to")]
+    amount: Option<Decimal>,
+
+    /// An optional memo to attach to the payment
+    #[arg(long)]
+    memo: Option<String>,
+
+    /// Where to write the unsigned proposal, to be carried
+    /// to the offline machine and signed there
+    #[arg(short, long)]
+    output: PathBuf,
+}
+
+impl ProposeOptions {
+    pub(crate) async fn run(
+        self,
+        wallet_dir: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        let params = get_wallet_network(wallet_dir.as_ref())?;
+        let (_, db_data) = get_db_paths(wallet_dir.as_ref());
+        let mut db_data = WalletDb::for_path(db_data, params.clone(), (), ())?;
+        let account = select_account(&db_data, self.account_id)?;
+        let request = build_transaction_request(
+            self.uri.as_deref(),
+            self.to.as_deref(),
+            self.amount,
+            self.memo.as_deref(),
+        )?;
+
+        let input_selector = GreedyInputSelector::new();
+        let change_strategy = SingleOutputChangeStrategy::new(
+            FeeRule::standard(),
+            None,
+            zcash_protocol::ShieldedProtocol::Orchard,
+            Zatoshis::ZERO,
+        );
+        let proposal = propose_transfer(
+            &mut db_data,
+            &params,
+            account.id(),
+            &input_selector,
+            &change_strategy,
+            request,
+            ConfirmationsPolicy::default(),
+        )
+        .map_err(error::Error::from)?;
+
+        // Self-describing so a proposal produced by one zecli build
+        // can be signed by a matching offline build. The account UUID
+        // is carried alongside the proposal itself so `sign` derives
+        // the spending key from the account this proposal was actually
+        // built against, instead of re-guessing it on a wallet that
+        // may hold more than one account.
+        let proto = proposal::Proposal::from_standard_proposal(&proposal);
+        let mut bytes = PROPOSAL_MAGIC.to_vec();
+        bytes.extend_from_slice(account.id().expose_uuid().as_bytes());
+        proto.encode(&mut bytes)?;
+        std::fs::write(&self.output, &bytes)?;
+        println!("Wrote unsigned proposal to {}", self.output.display());
+        Ok(())
+    }
+}
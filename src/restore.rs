@@ -0,0 +1,172 @@
+use bip0039::{English, Mnemonic};
+use clap::Args;
+use secrecy::{SecretVec, Zeroize};
+use tracing::warn;
+use zcash_client_backend::data_api::{WalletRead, WalletWrite};
+use zcash_client_sqlite::{AccountUuid, WalletDb};
+use zcash_keys::keys::UnifiedAddressRequest;
+use zcash_protocol::consensus;
+
+use crate::{
+    backup::BackupBundle,
+    config::{decrypt_armored, WalletConfig},
+    data::{get_db_paths, Network},
+    init::InitOptions,
+    remote::{tor_client, Servers},
+};
+
+// Upper bound on how many addresses we'll issue trying to reproduce
+// the one recorded in the backup, so a corrupted/foreign last_address
+// can't hang restore forever
+const MAX_ADDRESS_REGEN_ATTEMPTS: u32 = 10_000;
+
+
+#[derive(Debug, Args)]
+pub(crate) struct RestoreOptions {
+    /// Override the account name stored in the backup
+    /// (default: the name recorded when it was backed up)
+    #[arg(long)]
+    name: Option<String>,
+
+    /// Age identity file that can decrypt the backup bundle
+    #[arg(short, long)]
+    identity: String,
+
+    /// The encrypted backup bundle produced by `backup`
+    #[arg(short, long)]
+    backup: String,
+
+    /// Network the wallet is used with: \"test\" or \"main\"
+    /// (default: the network recorded in the backup). If given,
+    /// must match the backup's network
+    #[arg(short, long)]
+    #[arg(value_parser = Network::parse)]
+    network: Option<Network>,
+
+    /// The server used to resolve the restored birthday's tree state
+    /// (default is \"ecc\")
+    #[arg(short, long)]
+    #[arg(default_value = "ecc", value_parser = Servers::parse)]
+    server: Servers,
+}
+
+impl RestoreOptions {
+    pub(crate) async fn run(
+        self,
+        wallet_dir: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        let identities = age::IdentityFile::from_file(self.identity.clone())?
+            .into_identities()?;
+        let ciphertext = tokio::fs::read_to_string(&self.backup).await?;
+        let bundle_str = decrypt_armored(&identities, &ciphertext)?;
+        let bundle: BackupBundle = toml::from_str(&String::from_utf8(bundle_str)?)
+            .map_err(|_| anyhow::anyhow!("error reading backup bundle"))?;
+        let bundle_network = Network::parse(&bundle.network).map_err(|e| {
+            anyhow::anyhow!("Backup has an invalid network: {e}")
+        })?;
+        // Trust the network recorded in the backup rather than an
+        // unverified --network flag: restoring under the wrong network
+        // would silently reconstruct the wallet with the wrong HRPs
+        // and consensus params
+        if let Some(requested) = self.network {
+            if requested != bundle_network {
+                anyhow::bail!(
+                    "--network {requested:?} does not match the backup's network {bundle_network:?}"
+                );
+            }
+        }
+        let network = bundle_network;
+        let params = consensus::Network::from(network);
+        let mnemonic = <Mnemonic<English>>::from_phrase(&bundle.mnemonic)?;
+        let account_name = self
+            .name
+            .as_deref()
+            .or(bundle.account_name.as_deref())
+            .ok_or_else(|| {
+                anyhow::anyhow!("Backup has no account name; pass --name")
+            })?
+            .to_string();
+
+        let server = self.server.pick(params.clone())?;
+        let client = server.connect(|| tor_client(wallet_dir.as_ref())).await?;
+        let birthday = InitOptions::get_wallet_birthday(
+            client,
+            bundle.birthday.into(),
+            None,
+        )
+        .await?;
+
+        let recipients = age::IdentityFile::from_file(&self.identity)?.to_recipients()?;
+        WalletConfig::init_with_mnemonic(
+            wallet_dir.as_ref(),
+            recipients.iter().map(|r| r.as_ref() as _),
+            &mnemonic,
+            birthday.height(),
+            network.into(),
+        )?;
+
+        let seed = {
+            let mut seed = mnemonic.to_seed("");
+            let secret = seed.to_vec();
+            seed.zeroize();
+            SecretVec::new(secret)
+        };
+        let account_id = InitOptions::init_dbs(
+            params.clone(),
+            wallet_dir.as_ref(),
+            &account_name,
+            &seed,
+            birthday,
+            None,
+        )?;
+
+        // Re-derive the most recently issued diversified address so the
+        // restored wallet doesn't start back at its default address.
+        // Only the last one is recoverable, since that's all the bundle
+        // records; anything issued before it is lost. The source account
+        // may have issued any number of addresses beyond its default, so
+        // keep issuing until we reproduce it rather than checking once
+        if let Some(last_address) = &bundle.last_address {
+            let (_, db_data) = get_db_paths(wallet_dir.as_ref());
+            let mut db_data = WalletDb::for_path(db_data, params.clone(), (), ())?;
+            let account_id = AccountUuid::from_uuid(account_id);
+            // The new account already holds its default address, which
+            // is the recorded one if no other address was ever issued
+            let mut found = db_data
+                .get_last_generated_address_matching(
+                    account_id,
+                    UnifiedAddressRequest::AllAvailableKeys,
+                )?
+                .map(|address| address.encode(&params))
+                .as_ref()
+                == Some(last_address);
+            for _ in 0..MAX_ADDRESS_REGEN_ATTEMPTS {
+                if found {
+                    break;
+                }
+                let address = db_data
+                    .get_next_available_address(
+                        account_id,
+                        UnifiedAddressRequest::AllAvailableKeys,
+                    )?
+                    .map(|address| address.encode(&params));
+                if address.as_ref() == Some(last_address) {
+                    found = true;
+                    break;
+                }
+            }
+            if !found {
+                warn!(
+                    "Could not regenerate the address last issued before backup \
+                     ({last_address}) after {MAX_ADDRESS_REGEN_ATTEMPTS} attempts; \
+                     the restored account may be missing some issued addresses"
+                );
+            }
+        }
+
+        // create_account mints a new UUID for the restored account; it
+        // never reuses the source wallet's, so that's what we surface
+        println!("Restored account {account_id} from backup");
+        Ok(())
+    }
+}
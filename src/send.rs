@@ -0,0 +1,167 @@
+use clap::Args;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+use zcash_address::ZcashAddress;
+use zcash_client_backend::{
+    data_api::wallet::{
+        create_proposed_transactions, input_selection::GreedyInputSelector,
+        propose_transfer, ConfirmationsPolicy,
+    },
+    fees::standard::SingleOutputChangeStrategy,
+    wallet::OvkPolicy,
+};
+use zcash_client_sqlite::WalletDb;
+use zcash_primitives::{memo::MemoBytes, transaction::fees::zip317::FeeRule};
+use zcash_protocol::value::Zatoshis;
+use zip321::{Payment, TransactionRequest};
+
+use crate::{
+    balance::select_account,
+    broadcast::broadcast_proposed_transactions,
+    config::{get_wallet_network, WalletConfig},
+    data::get_db_paths,
+    error,
+    remote::Servers,
+    ui::{format_zec, zec_to_zatoshis},
+};
+
+#[derive(Debug, Args)]
+pub(crate) struct SendOptions {
+    /// The UUID of the account if multiple exist
+    account_id: Option<Uuid>,
+
+    /// Age identity file to decrypt the mnemonic phrase with
+    #[arg(short, long)]
+    identity: String,
+
+    /// A raw ZIP-321 payment URI (\"zip321:...\"). Mutually
+    /// exclusive with --to/--amount/--memo
+    #[arg(long, conflicts_with_all = ["to", "amount", "memo"])]
+    uri: Option<String>,
+
+    /// Recipient unified/transparent/shielded address
+    #[arg(long, requires = "amount")]
+    to: Option<String>,
+
+    /// Amount to send, in ZEC
+    #[arg(long, requires = "to")]
+    amount: Option<Decimal>,
+
+    /// An optional memo to attach to the payment
+    #[arg(long)]
+    memo: Option<String>,
+
+    /// The server to broadcast the transaction through
+    /// (default is \"ecc\")
+    #[arg(short, long)]
+    #[arg(default_value = "ecc", value_parser = Servers::parse)]
+    server: Servers,
+}
+
+impl SendOptions {
+    pub(crate) async fn run(
+        self,
+        wallet_dir: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        let params = get_wallet_network(wallet_dir.as_ref())?;
+        let (_, db_data) = get_db_paths(wallet_dir.as_ref());
+        let mut db_data = WalletDb::for_path(db_data, params.clone(), (), ())?;
+        let account = select_account(&db_data, self.account_id)?;
+        let request = build_transaction_request(
+            self.uri.as_deref(),
+            self.to.as_deref(),
+            self.amount,
+            self.memo.as_deref(),
+        )?;
+
+        // Mirror `init`'s recipient handling in reverse: we already
+        // have an age identity on disk, so decrypt rather than encrypt
+        let usk = WalletConfig::derive_spending_key(
+            wallet_dir.as_ref(),
+            &self.identity,
+            &params,
+            account.account_index(),
+        )?;
+
+        let input_selector = GreedyInputSelector::new();
+        let change_strategy = SingleOutputChangeStrategy::new(
+            FeeRule::standard(),
+            None,
+            zcash_protocol::ShieldedProtocol::Orchard,
+            Zatoshis::ZERO,
+        );
+        let proposal = propose_transfer(
+            &mut db_data,
+            &params,
+            account.id(),
+            &input_selector,
+            &change_strategy,
+            request,
+            ConfirmationsPolicy::default(),
+        )
+        .map_err(error::Error::from)?;
+        let fee = proposal.fee_amount()?;
+
+        let txids = create_proposed_transactions(
+            &mut db_data,
+            &params,
+            None,
+            &usk,
+            OvkPolicy::Sender,
+            &proposal,
+        )
+        .map_err(error::Error::from)?;
+
+        broadcast_proposed_transactions(
+            wallet_dir.as_ref(),
+            params,
+            self.server,
+            txids.iter(),
+            |txid| {
+                let raw_tx = db_data.get_transaction(txid)?.ok_or_else(|| {
+                    anyhow::anyhow!("Created transaction {txid} is missing")
+                })?;
+                let mut raw_tx_bytes = vec![];
+                raw_tx.write(&mut raw_tx_bytes)?;
+                Ok(raw_tx_bytes)
+            },
+        )
+        .await?;
+        println!(
+            "Sent {} (fee: {})",
+            txids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            format_zec(fee)
+        );
+        Ok(())
+    }
+
+}
+
+/// Builds a `TransactionRequest` from either a raw ZIP-321 URI or a
+/// `--to`/`--amount`/`--memo` triple. Shared with `propose`, which
+/// accepts the same recipient arguments for the air-gapped signing flow.
+pub(crate) fn build_transaction_request(
+    uri: Option<&str>,
+    to: Option<&str>,
+    amount: Option<Decimal>,
+    memo: Option<&str>,
+) -> Result<TransactionRequest, anyhow::Error> {
+    if let Some(uri) = uri {
+        return Ok(TransactionRequest::from_uri(uri).map_err(error::Error::from)?);
+    }
+    let to = to.ok_or_else(|| anyhow::anyhow!("--to or --uri is required"))?;
+    let amount =
+        amount.ok_or_else(|| anyhow::anyhow!("--amount or --uri is required"))?;
+    let value = zec_to_zatoshis(amount)?;
+    let address = ZcashAddress::try_from_encoded(to)?;
+    let memo = memo
+        .map(|m| MemoBytes::from_bytes(m.as_bytes()))
+        .transpose()?;
+    let payment = Payment::new(address, value, memo, None, None, vec![])
+        .ok_or_else(|| anyhow::anyhow!("Invalid payment parameters"))?;
+    Ok(TransactionRequest::new(vec![payment]).map_err(error::Error::from)?)
+}
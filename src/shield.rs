@@ -0,0 +1,142 @@
+use clap::Args;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+use zcash_client_backend::{
+    data_api::wallet::{
+        create_proposed_transactions, propose_shielding, ConfirmationsPolicy,
+    },
+    fees::standard::SingleOutputChangeStrategy,
+    wallet::OvkPolicy,
+};
+use zcash_client_sqlite::WalletDb;
+use zcash_primitives::transaction::fees::zip317::FeeRule;
+use zcash_protocol::value::Zatoshis;
+
+use crate::{
+    balance::select_account,
+    broadcast::broadcast_proposed_transactions,
+    config::{get_wallet_network, WalletConfig},
+    data::get_db_paths,
+    error,
+    remote::Servers,
+    ui::{format_zec, zec_to_zatoshis},
+};
+
+#[derive(Debug, Args)]
+pub(crate) struct ShieldOptions {
+    /// The UUID of the account if multiple exist
+    account_id: Option<Uuid>,
+
+    /// Age identity file to decrypt the mnemonic phrase with
+    #[arg(short, long)]
+    identity: String,
+
+    /// Minimum unshielded balance required before sweeping
+    /// (default is the ZIP-317 marginal fee, so dust is never
+    /// shielded at a loss)
+    #[arg(long)]
+    threshold: Option<Decimal>,
+
+    /// The server to broadcast the transaction through
+    /// (default is \"ecc\")
+    #[arg(short, long)]
+    #[arg(default_value = "ecc", value_parser = Servers::parse)]
+    server: Servers,
+}
+
+impl ShieldOptions {
+    pub(crate) async fn run(
+        self,
+        wallet_dir: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        let params = get_wallet_network(wallet_dir.as_ref())?;
+        let (_, db_data) = get_db_paths(wallet_dir.as_ref());
+        let mut db_data = WalletDb::for_path(db_data, params.clone(), (), ())?;
+        let account = select_account(&db_data, self.account_id)?;
+
+        let threshold = match self.threshold {
+            Some(zec) => zec_to_zatoshis(zec)?,
+            None => FeeRule::standard().marginal_fee(),
+        };
+
+        let transparent_balances =
+            db_data.get_transparent_balances(account.id(), None)?;
+        let unshielded = transparent_balances
+            .values()
+            .map(|b| b.spendable_value())
+            .fold(Zatoshis::ZERO, |acc, v| (acc + v).unwrap_or(acc));
+        if unshielded < threshold {
+            println!(
+                "Unshielded balance {} is below the {} threshold, nothing to shield",
+                format_zec(unshielded),
+                format_zec(threshold)
+            );
+            return Ok(());
+        }
+
+        let usk = WalletConfig::derive_spending_key(
+            wallet_dir.as_ref(),
+            &self.identity,
+            &params,
+            account.account_index(),
+        )?;
+
+        let change_strategy = SingleOutputChangeStrategy::new(
+            FeeRule::standard(),
+            None,
+            zcash_protocol::ShieldedProtocol::Orchard,
+            Zatoshis::ZERO,
+        );
+        // Sweep the UTXOs of every transparent address the account holds
+        let from_addrs: Vec<_> =
+            transparent_balances.keys().cloned().collect();
+        // Shield into the account's own unified internal (change) address
+        let proposal = propose_shielding(
+            &mut db_data,
+            &params,
+            &change_strategy,
+            threshold,
+            &from_addrs,
+            account.id(),
+            ConfirmationsPolicy::default(),
+        )
+        .map_err(error::Error::from)?;
+        let fee = proposal.fee_amount()?;
+
+        let txids = create_proposed_transactions(
+            &mut db_data,
+            &params,
+            None,
+            &usk,
+            OvkPolicy::Sender,
+            &proposal,
+        )
+        .map_err(error::Error::from)?;
+
+        broadcast_proposed_transactions(
+            wallet_dir.as_ref(),
+            params,
+            self.server,
+            txids.iter(),
+            |txid| {
+                let raw_tx = db_data.get_transaction(txid)?.ok_or_else(|| {
+                    anyhow::anyhow!("Created transaction {txid} is missing")
+                })?;
+                let mut raw_tx_bytes = vec![];
+                raw_tx.write(&mut raw_tx_bytes)?;
+                Ok(raw_tx_bytes)
+            },
+        )
+        .await?;
+        println!(
+            "Shielded {} (fee: {})",
+            txids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            format_zec(fee)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use clap::Args;
+use prost::Message;
+use std::path::PathBuf;
+use uuid::Uuid;
+use zcash_client_backend::{
+    data_api::wallet::create_proposed_transactions, proto::proposal, wallet::OvkPolicy,
+};
+use zcash_client_sqlite::WalletDb;
+
+use crate::{
+    balance::select_account,
+    config::{get_wallet_network, WalletConfig},
+    data::get_db_paths,
+    error,
+    propose::PROPOSAL_MAGIC,
+};
+
+#[derive(Debug, Args)]
+pub(crate) struct SignOptions {
+    /// Age identity file to decrypt the mnemonic phrase with
+    #[arg(short, long)]
+    identity: String,
+
+    /// The unsigned proposal file produced by `propose`
+    #[arg(short, long)]
+    proposal: PathBuf,
+
+    /// Where to write the signed raw transaction(s), to be
+    /// carried back to the online machine and broadcast
+    #[arg(short, long)]
+    output: PathBuf,
+}
+
+impl SignOptions {
+    pub(crate) async fn run(
+        self,
+        wallet_dir: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        let params = get_wallet_network(wallet_dir.as_ref())?;
+        let (_, db_data) = get_db_paths(wallet_dir.as_ref());
+        let mut db_data = WalletDb::for_path(db_data, params.clone(), (), ())?;
+
+        let bytes = std::fs::read(&self.proposal)?;
+        let bytes = bytes.strip_prefix(PROPOSAL_MAGIC).ok_or_else(|| {
+            anyhow::anyhow!("{} is not a zecli proposal file", self.proposal.display())
+        })?;
+        // `propose` prepends the account UUID it built the proposal
+        // against, so `sign` derives the spending key for that same
+        // account instead of guessing (which only happened to work
+        // on a wallet holding a single account)
+        if bytes.len() < 16 {
+            anyhow::bail!(
+                "{} is not a zecli proposal file",
+                self.proposal.display()
+            );
+        }
+        let (account_id_bytes, bytes) = bytes.split_at(16);
+        let account_id = Uuid::from_slice(account_id_bytes)
+            .map_err(|e| anyhow::anyhow!("Invalid account UUID in proposal: {e}"))?;
+        let proto = proposal::Proposal::decode(bytes)?;
+        let proposal = proto
+            .try_into_standard_proposal(&db_data)
+            .map_err(|e| anyhow::anyhow!("Invalid proposal: {e:?}"))?;
+        let account = select_account(&db_data, Some(account_id))?;
+
+        // The seed never needs to touch the online host: it's
+        // decrypted here, offline, for just long enough to sign
+        let usk = WalletConfig::derive_spending_key(
+            wallet_dir.as_ref(),
+            &self.identity,
+            &params,
+            account.account_index(),
+        )?;
+
+        let txids = create_proposed_transactions(
+            &mut db_data,
+            &params,
+            None,
+            &usk,
+            OvkPolicy::Sender,
+            &proposal,
+        )
+        .map_err(error::Error::from)?;
+
+        // Length-prefix each raw transaction so `broadcast` can
+        // split a proposal that produced more than one transaction
+        let mut out = vec![];
+        for txid in txids.iter() {
+            let raw_tx = db_data.get_transaction(*txid)?.ok_or_else(|| {
+                anyhow::anyhow!("Signed transaction {txid} is missing")
+            })?;
+            let mut raw_tx_bytes = vec![];
+            raw_tx.write(&mut raw_tx_bytes)?;
+            out.extend((raw_tx_bytes.len() as u32).to_le_bytes());
+            out.extend(raw_tx_bytes);
+        }
+        std::fs::write(&self.output, &out)?;
+        println!("Wrote signed transaction(s) to {}", self.output.display());
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use clap::Args;
+use tracing::info;
+use zcash_client_backend::{
+    data_api::{chain::scan_cached_blocks, scanning::ScanPriority, WalletRead, WalletWrite},
+    proto::service,
+};
+use zcash_client_sqlite::{chain::init::init_blockmeta_db, FsBlockDb};
+use zcash_protocol::consensus;
+
+use crate::{
+    config::get_wallet_network,
+    data::{get_blockdb_path, init_dbs},
+    remote::{tor_client, Servers},
+};
+
+// Default number of blocks to scan per batch. Picked to
+// keep each scan within a reasonable amount of memory/time.
+const DEFAULT_BATCH_SIZE: u32 = 1000;
+
+#[derive(Debug, Args)]
+pub(crate) struct SyncOptions {
+    /// The server to sync from
+    /// (default is \"ecc\")
+    #[arg(short, long)]
+    #[arg(default_value = "ecc", value_parser = Servers::parse)]
+    server: Servers,
+
+    /// Number of blocks scanned per batch
+    #[arg(long, default_value_t = DEFAULT_BATCH_SIZE)]
+    batch_size: u32,
+}
+
+impl SyncOptions {
+    pub(crate) async fn run(
+        self,
+        wallet_dir: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        if self.batch_size == 0 {
+            anyhow::bail!("--batch-size must be greater than 0");
+        }
+
+        let params = get_wallet_network(wallet_dir.as_ref())?;
+        let server = self.server.pick(params.clone())?;
+        let mut client =
+            server.connect(|| tor_client(wallet_dir.as_ref())).await?;
+
+        // Block cache lives alongside the wallet DB so that
+        // a resumed sync can pick up where the last one left off
+        let blockdb_path = get_blockdb_path(wallet_dir.as_ref());
+        init_blockmeta_db(&blockdb_path)?;
+        let mut block_cache = FsBlockDb::for_path(&blockdb_path)?;
+        let mut db_data = init_dbs(params.clone(), wallet_dir.as_ref())?;
+
+        // Refresh the chain tip so the scan/recovery progress
+        // fractions shown in `balance` become meaningful
+        let chain_tip: u32 = client
+            .get_latest_block(service::ChainSpec::default())
+            .await?
+            .into_inner()
+            .height
+            .try_into()
+            .expect("block heights must fit into u32");
+        db_data.update_chain_tip(chain_tip.into())?;
+
+        loop {
+            let scan_ranges = db_data.suggest_scan_ranges()?;
+            let Some(scan_range) = scan_ranges.into_iter().find(|r| {
+                r.priority() != ScanPriority::Scanned
+            }) else {
+                break;
+            };
+            info!(
+                "Downloading and scanning {:?} ({:?})",
+                scan_range.block_range(),
+                scan_range.priority()
+            );
+            let mut from = scan_range.block_range().start;
+            while from < scan_range.block_range().end {
+                let to = std::cmp::min(
+                    from + self.batch_size,
+                    scan_range.block_range().end,
+                );
+                let request = service::BlockRange {
+                    start: Some(service::BlockId {
+                        height: u64::from(from),
+                        ..Default::default()
+                    }),
+                    end: Some(service::BlockId {
+                        height: u64::from(to) - 1,
+                        ..Default::default()
+                    }),
+                };
+                let mut stream =
+                    client.get_block_range(request).await?.into_inner();
+                let mut blocks = vec![];
+                while let Some(block) = stream.message().await? {
+                    blocks.push(block);
+                }
+                block_cache.write_blocks(&blocks)?;
+                scan_cached_blocks(
+                    &params,
+                    &block_cache,
+                    &mut db_data,
+                    from,
+                    blocks.len(),
+                )?;
+                from = to;
+            }
+        }
+        info!("Sync complete");
+        Ok(())
+    }
+}
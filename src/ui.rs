@@ -0,0 +1,29 @@
+use rust_decimal::{
+    prelude::{FromPrimitive, ToPrimitive},
+    Decimal,
+};
+use zcash_protocol::value::{Zatoshis, COIN};
+
+/// Column width used to wrap long-form text (the address and the
+/// detail lines) in `balance`'s detailed output.
+pub(crate) const TEXT_WIDTH: usize = 72;
+
+/// Formats a zatoshi amount as a ZEC value, e.g. `1.23456789 ZEC`.
+pub(crate) fn format_zec(value: Zatoshis) -> String {
+    let zec = Decimal::from(value.into_u64()) / Decimal::from(COIN);
+    format!("{zec} ZEC")
+}
+
+/// Converts a ZEC amount parsed as a `Decimal` into `Zatoshis`.
+///
+/// Decimal (rather than `f64`) keeps the conversion exact for the
+/// fractional ZEC amounts users type on the command line; rounding
+/// still happens here because a `Decimal` can express more than 8
+/// places after the point and zatoshis cannot.
+pub(crate) fn zec_to_zatoshis(zec: Decimal) -> Result<Zatoshis, anyhow::Error> {
+    let zatoshis = (zec * Decimal::from_u64(COIN).unwrap())
+        .round()
+        .to_u64()
+        .ok_or_else(|| anyhow::anyhow!("Invalid amount: {zec}"))?;
+    Ok(Zatoshis::from_u64(zatoshis)?)
+}